@@ -0,0 +1,360 @@
+use crate::cli::{AddressTypeArg, ReportFormat};
+use crate::consensus::verify_transaction;
+use crate::history::{list_transactions_by_address, summarize_spendable};
+use crate::network::{detect_network, guard_network};
+use crate::report::TransactionReport;
+use crate::rpc::ReconnectingClient;
+use crate::wallet::{create_or_import_descriptor_wallet, create_or_load_wallet, wallet_client};
+use bitcoincore_rpc::bitcoin::consensus::deserialize;
+use bitcoincore_rpc::bitcoin::{Address, Amount, Network, SignedAmount};
+use bitcoincore_rpc::{Auth, RpcApi};
+use serde_json::Value;
+use std::fs::File;
+
+/// Shared RPC endpoint parameters, threaded through from the CLI flags/env vars.
+pub struct RpcConfig {
+    pub rpc_url: String,
+    pub rpc_user: String,
+    pub rpc_pass: String,
+    pub miner_wallet: String,
+    pub trader_wallet: String,
+}
+
+impl RpcConfig {
+    pub fn base_client(&self) -> Result<ReconnectingClient, bitcoincore_rpc::Error> {
+        ReconnectingClient::new(
+            self.rpc_url.clone(),
+            Auth::UserPass(self.rpc_user.clone(), self.rpc_pass.clone()),
+        )
+    }
+
+    pub fn miner_client(&self) -> Result<ReconnectingClient, bitcoincore_rpc::Error> {
+        wallet_client(&self.rpc_url, &self.rpc_user, &self.rpc_pass, &self.miner_wallet)
+    }
+
+    pub fn trader_client(&self) -> Result<ReconnectingClient, bitcoincore_rpc::Error> {
+        wallet_client(&self.rpc_url, &self.rpc_user, &self.rpc_pass, &self.trader_wallet)
+    }
+
+    pub fn wallet_client(&self, wallet_name: &str) -> Result<ReconnectingClient, bitcoincore_rpc::Error> {
+        wallet_client(&self.rpc_url, &self.rpc_user, &self.rpc_pass, wallet_name)
+    }
+}
+
+/// `setup`: create/load the Miner and Trader wallets.
+pub fn setup(cfg: &RpcConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = cfg.base_client()?;
+    create_or_load_wallet(&rpc, &cfg.miner_wallet)?;
+    create_or_load_wallet(&rpc, &cfg.trader_wallet)?;
+    Ok(())
+}
+
+/// `mine <count>`: mine `count` blocks to a fresh Miner address.
+pub fn mine(cfg: &RpcConfig, count: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = cfg.base_client()?;
+    let network = detect_network(&rpc)?;
+    guard_network(network)?;
+
+    let miner_rpc = cfg.miner_client()?;
+    let miner_address_unchecked = miner_rpc.get_new_address(Some("Mining Reward"), None)?;
+    let miner_address = miner_address_unchecked.require_network(network)?;
+    println!("Mining {} block(s) to {}...", count, miner_address);
+    miner_rpc.generate_to_address(count, &miner_address)?;
+    Ok(())
+}
+
+/// `send --to <addr> --amount <btc>`: send BTC from the Miner's wallet.
+pub fn send(cfg: &RpcConfig, to: &str, amount: f64) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = cfg.base_client()?;
+    let network = detect_network(&rpc)?;
+    guard_network(network)?;
+
+    let miner_rpc = cfg.miner_client()?;
+    let destination = to.parse::<Address<_>>()?.require_network(network)?;
+    let txid = miner_rpc.send_to_address(
+        &destination,
+        Amount::from_btc(amount)?,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    println!("Transaction sent! TXID: {}", txid);
+    Ok(())
+}
+
+/// `balance [--wallet <name>]`: print a wallet's balance (Miner by default).
+pub fn balance(cfg: &RpcConfig, wallet: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let wallet_name = wallet.unwrap_or(&cfg.miner_wallet);
+    let wallet_rpc = cfg.wallet_client(wallet_name)?;
+    let balance = wallet_rpc.get_balance(None, None)?;
+    println!("{} wallet balance: {} BTC", wallet_name, balance.to_btc());
+    Ok(())
+}
+
+/// `new-address [--wallet <name>] [--type <type>]`: generate a new receiving
+/// address, optionally of a specific address type.
+pub fn new_address(
+    cfg: &RpcConfig,
+    wallet: Option<&str>,
+    address_type: AddressTypeArg,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = cfg.base_client()?;
+    let network = detect_network(&rpc)?;
+
+    let wallet_name = wallet.unwrap_or(&cfg.miner_wallet);
+    let wallet_rpc = cfg.wallet_client(wallet_name)?;
+    let address_unchecked = wallet_rpc.get_new_address(None, Some(address_type.into()))?;
+    let address = address_unchecked.require_network(network)?;
+    println!("{} address: {}", wallet_name, address);
+    Ok(())
+}
+
+/// `history --wallet <name> --address <addr> --limit <n>`: show a wallet's
+/// transaction history for one address, plus its spendable/immature UTXO summary.
+pub fn history(
+    cfg: &RpcConfig,
+    wallet: Option<&str>,
+    address: &str,
+    limit: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = cfg.base_client()?;
+    let network = detect_network(&rpc)?;
+
+    let wallet_name = wallet.unwrap_or(&cfg.miner_wallet);
+    let wallet_rpc = cfg.wallet_client(wallet_name)?;
+    let target_address = address.parse::<Address<_>>()?.require_network(network)?;
+
+    let txs = list_transactions_by_address(&wallet_rpc, &target_address, network, limit)?;
+    println!("Transactions to {} ({} wallet):", target_address, wallet_name);
+    for tx in &txs {
+        let block = tx
+            .blockheight
+            .map_or(String::new(), |h| format!(" block={}", h));
+        println!(
+            "  {} {:<8} {:>14.8} BTC  confirmations={}{}",
+            tx.txid, tx.category, tx.amount, tx.confirmations, block
+        );
+    }
+
+    let summary = summarize_spendable(&wallet_rpc, &target_address, network)?;
+    println!(
+        "Spendable: {:.8} BTC, immature coinbase: {:.8} BTC across {} output(s)",
+        summary.spendable_btc, summary.immature_btc, summary.immature_utxo_count
+    );
+
+    Ok(())
+}
+
+/// `import-wallet --name <name> --mnemonic <phrase> --derivation-path <path>`:
+/// provision a descriptor wallet deterministically from a BIP39 mnemonic.
+pub fn import_wallet(
+    cfg: &RpcConfig,
+    name: &str,
+    mnemonic: &str,
+    derivation_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = cfg.base_client()?;
+    let network = detect_network(&rpc)?;
+    let wallet_rpc = cfg.wallet_client(name)?;
+    create_or_import_descriptor_wallet(&rpc, &wallet_rpc, name, mnemonic, derivation_path, network)
+}
+
+/// `report`: run the original fixed demo end-to-end (provision wallets, mine,
+/// send, confirm) and write the resulting transaction's details to
+/// `../out.txt`.
+pub fn report(cfg: &RpcConfig, format: ReportFormat) -> Result<(), Box<dyn std::error::Error>> {
+    // 1. Connect to Bitcoin Core RPC
+    let rpc = cfg.base_client()?;
+
+    // 2. Work out which chain we're actually talking to before we do anything
+    // that mines blocks or moves funds. Mainnet is never acceptable here.
+    let network = detect_network(&rpc)?;
+    guard_network(network)?;
+
+    // 3. Create/Load the wallets: 'Miner' and 'Trader'
+    create_or_load_wallet(&rpc, &cfg.miner_wallet)?;
+    create_or_load_wallet(&rpc, &cfg.trader_wallet)?;
+
+    // Create wallet-specific clients to interact with each wallet
+    let miner_rpc = cfg.miner_client()?;
+    let trader_rpc = cfg.trader_client()?;
+
+    // 4. Generate a new address for the Miner
+    let miner_address_unchecked = miner_rpc.get_new_address(Some("Mining Reward"), None)?;
+    let miner_address = miner_address_unchecked.require_network(network)?;
+    println!("Miner address for rewards: {}", miner_address);
+
+    // 5. Mine blocks to make the coinbase reward spendable
+    // A coinbase transaction (block reward) is only spendable after 100 confirmations.
+    // To get N spendable coinbase rewards, we need to mine 100 + N blocks. On regtest
+    // we control the hashrate so we can just mine them instantly; on test/signet we
+    // don't own enough hashpower to do that, so no blocks are generated and the
+    // Miner wallet has to already hold spendable funds.
+    if network == Network::Regtest {
+        println!("Mining 110 blocks to mature coinbase rewards...");
+        miner_rpc.generate_to_address(110, &miner_address)?;
+    } else {
+        println!(
+            "Skipping instant block generation on {:?}: using the Miner wallet's \
+             existing funds instead.",
+            network
+        );
+    }
+
+    // 6. Print the Miner's balance
+    let miner_balance = miner_rpc.get_balance(None, None)?;
+    println!("Miner wallet balance: {} BTC", miner_balance.to_btc());
+
+    // 7. Create a receiving address for the Trader
+    let trader_address_unchecked = trader_rpc.get_new_address(Some("Received"), None)?;
+    let trader_address = trader_address_unchecked.require_network(network)?;
+    println!("Trader receiving address: {}", trader_address);
+
+    // 8. Send 0.1 BTC from Miner to Trader.
+    // Note: We send a small amount to ensure there are sufficient funds,
+    // as the wallet balance can vary between runs.
+    let txid = miner_rpc.send_to_address(
+        &trader_address,
+        Amount::from_btc(0.1)?,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    println!("Transaction sent! TXID: {}", txid);
+
+    // 9. Fetch the unconfirmed transaction from the mempool
+    let mempool_entry = rpc.call::<Value>("getmempoolentry", &[txid.to_string().into()])?;
+    println!(
+        "Mempool entry for tx {}:\n{}",
+        txid,
+        serde_json::to_string_pretty(&mempool_entry)?
+    );
+
+    // 10. Confirm the transaction by mining 1 block
+    if network == Network::Regtest {
+        println!("Mining 1 block to confirm the transaction...");
+        miner_rpc.generate_to_address(1, &miner_address)?;
+    } else {
+        println!(
+            "Waiting for the transaction to confirm naturally on {:?}...",
+            network
+        );
+        while miner_rpc.get_transaction(&txid, Some(true))?.info.confirmations < 1 {
+            std::thread::sleep(std::time::Duration::from_secs(30));
+        }
+    }
+
+    // 11. Fetch the confirmed transaction details
+    let tx_info = miner_rpc.get_transaction(&txid, Some(true))?;
+    let decoded_tx: bitcoincore_rpc::bitcoin::Transaction = deserialize(&tx_info.hex)?;
+
+    // 12. Verify every input against consensus script rules before we trust
+    // this transaction enough to document it. A node can accept a transaction
+    // under its own policy while a stricter consensus check would reject it,
+    // so this is a stronger guarantee than "my node accepted it".
+    let input_checks = verify_transaction(&miner_rpc, &decoded_tx)?;
+    let mut any_failed = false;
+    for check in &input_checks {
+        println!(
+            "Input {} ({}:{}): {}",
+            check.index,
+            check.outpoint.txid,
+            check.outpoint.vout,
+            if check.passed { "PASS" } else { "FAIL" }
+        );
+        any_failed |= !check.passed;
+    }
+    if any_failed {
+        return Err("consensus verification failed for one or more inputs; aborting report".into());
+    }
+
+    // --- Extract details for out.txt ---
+
+    // a. Transaction ID
+    let final_txid = tx_info.info.txid;
+
+    // b. Miner's Input Address & Amount
+    // For simplicity, we'll display the address from the first input.
+    // Note: A transaction can have multiple inputs.
+    let previous_outpoint = decoded_tx.input[0].previous_output;
+    let input_txid = previous_outpoint.txid;
+    let input_vout_n = previous_outpoint.vout;
+    let prev_tx_info = miner_rpc.get_transaction(&input_txid, Some(true))?;
+    let prev_decoded_tx: bitcoincore_rpc::bitcoin::Transaction = deserialize(&prev_tx_info.hex)?;
+    let input_utxo = &prev_decoded_tx.output[input_vout_n as usize];
+    let miner_input_address = Address::from_script(input_utxo.script_pubkey.as_ref(), network)?;
+    // The total input amount is the sum of all outputs plus the fee.
+    let fee = tx_info.fee.unwrap_or(SignedAmount::from_sat(0)).abs();
+    let total_output_amount: Amount = decoded_tx.output.iter().map(|o| o.value).sum();
+    let miner_input_amount = total_output_amount + fee.to_unsigned()?;
+
+    // c. Trader's Output Address & Amount
+    let trader_output = decoded_tx
+        .output
+        .iter()
+        .find(|vout| {
+            Address::from_script(vout.script_pubkey.as_ref(), network)
+                .is_ok_and(|addr| addr == trader_address)
+        })
+        .ok_or("Trader output not found")?;
+    let trader_output_address = Address::from_script(trader_output.script_pubkey.as_ref(), network)?;
+    let trader_output_amount = trader_output.value;
+
+    // d. Miner's Change Address & Amount
+    let miner_change_output = decoded_tx
+        .output
+        .iter()
+        // A more robust way to find the change output is to find an output
+        // that is a valid address but is not the trader's address.
+        .find(|vout| {
+            if let Ok(addr) = Address::from_script(vout.script_pubkey.as_ref(), network) {
+                addr != trader_address
+            } else {
+                false
+            }
+        });
+
+    // f. Block height and hash
+    let block_height = tx_info.info.blockheight.ok_or("Block height not found")?;
+    let block_hash = tx_info.info.blockhash.ok_or("Block hash not found")?;
+
+    // d (cont). Miner's Change Address & Amount
+    let (miner_change_address, miner_change_amount) = match miner_change_output {
+        Some(change_output) => {
+            let address = Address::from_script(change_output.script_pubkey.as_ref(), network)?;
+            (address.to_string(), change_output.value)
+        }
+        None => ("None".to_string(), Amount::ZERO),
+    };
+
+    let report = TransactionReport {
+        txid: final_txid.to_string(),
+        miner_input_address: miner_input_address.to_string(),
+        miner_input_amount,
+        trader_output_address: trader_output_address.to_string(),
+        trader_output_amount,
+        miner_change_address,
+        miner_change_amount,
+        fee,
+        block_height,
+        block_hash: block_hash.to_string(),
+    };
+
+    // 13. Write the report to ../out.txt in the requested format.
+    let mut file = File::create("../out.txt")?;
+    match format {
+        ReportFormat::Txt => report.write_txt(&mut file)?,
+        ReportFormat::Json => report.write_json(&mut file)?,
+        ReportFormat::Table => report.write_table(&mut file)?,
+    }
+
+    println!("Successfully wrote transaction details to ../out.txt");
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use bitcoincore_rpc::bitcoin::consensus::{deserialize, serialize};
+use bitcoincore_rpc::bitcoin::{OutPoint, Transaction, TxOut};
+use bitcoincore_rpc::RpcApi;
+
+/// Result of checking a single input's script against consensus rules.
+pub struct InputVerification {
+    pub index: usize,
+    pub outpoint: OutPoint,
+    pub passed: bool,
+}
+
+/// Fetch input `index`'s previous output by looking up the funding
+/// transaction it spends.
+fn previous_output<C: RpcApi>(
+    rpc: &C,
+    tx: &Transaction,
+    index: usize,
+) -> Result<TxOut, Box<dyn std::error::Error>> {
+    let outpoint = tx.input[index].previous_output;
+    let prev_tx_info = rpc.get_transaction(&outpoint.txid, Some(true))?;
+    let prev_tx: Transaction = deserialize(&prev_tx_info.hex)?;
+    Ok(prev_tx.output[outpoint.vout as usize].clone())
+}
+
+/// Verify every input of `tx` against consensus script rules via
+/// `bitcoinconsensus`, fetching each input's previous output through `rpc`.
+/// Returns one `InputVerification` per input, in the transaction's input order.
+pub fn verify_transaction<C: RpcApi>(
+    rpc: &C,
+    tx: &Transaction,
+) -> Result<Vec<InputVerification>, Box<dyn std::error::Error>> {
+    let serialized_tx = serialize(tx);
+    tx.input
+        .iter()
+        .enumerate()
+        .map(|(index, input)| {
+            let prev_output = previous_output(rpc, tx, index)?;
+            let passed = prev_output
+                .script_pubkey
+                .verify(index, prev_output.value, &serialized_tx)
+                .is_ok();
+            Ok(InputVerification {
+                index,
+                outpoint: input.previous_output,
+                passed,
+            })
+        })
+        .collect()
+}
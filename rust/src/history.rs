@@ -0,0 +1,121 @@
+use bitcoincore_rpc::bitcoin::{Address, Amount, Network};
+use bitcoincore_rpc::json::GetTransactionResultDetailCategory;
+use bitcoincore_rpc::{Error, RpcApi};
+use serde::Serialize;
+
+/// One entry from a wallet's transaction history that touched a given address.
+#[derive(Debug, Serialize)]
+pub struct TxSummary {
+    pub txid: String,
+    pub category: String,
+    pub amount: f64,
+    pub confirmations: i32,
+    pub blockheight: Option<u32>,
+}
+
+/// Page through `listtransactions`, keeping only entries whose address
+/// matches `address`, until `limit` matches are found or history is exhausted.
+pub fn list_transactions_by_address<C: RpcApi>(
+    rpc: &C,
+    address: &Address,
+    network: Network,
+    limit: usize,
+) -> Result<Vec<TxSummary>, Error> {
+    const PAGE_SIZE: usize = 100;
+
+    let mut matches = Vec::new();
+    if limit == 0 {
+        return Ok(matches);
+    }
+    let mut skip = 0usize;
+    loop {
+        let page = rpc.list_transactions(None, Some(PAGE_SIZE), Some(skip), Some(true))?;
+        if page.is_empty() {
+            break;
+        }
+        for entry in &page {
+            let entry_address = entry
+                .detail
+                .address
+                .clone()
+                .and_then(|a| a.require_network(network).ok());
+            if entry_address.as_ref() != Some(address) {
+                continue;
+            }
+            matches.push(TxSummary {
+                txid: entry.info.txid.to_string(),
+                category: format!("{:?}", entry.detail.category).to_lowercase(),
+                amount: entry.detail.amount.to_btc(),
+                confirmations: entry.info.confirmations,
+                blockheight: entry.info.blockheight,
+            });
+            if matches.len() >= limit {
+                return Ok(matches);
+            }
+        }
+        skip += page.len();
+    }
+    Ok(matches)
+}
+
+/// Spendable vs. immature-coinbase balance for a single address.
+#[derive(Debug, Serialize)]
+pub struct UtxoSummary {
+    pub address: String,
+    pub spendable_btc: f64,
+    pub immature_btc: f64,
+    pub immature_utxo_count: usize,
+}
+
+/// Summarize `address`'s spendable vs. immature-coinbase balance.
+///
+/// `listunspent` already excludes coinbase outputs that haven't matured
+/// (Core's `AvailableCoins` skips any coinbase with `GetBlocksToMaturity() >
+/// 0`), so everything it returns for this address is genuinely spendable
+/// right now — it can't be used to *find* immature coinbase outputs, only to
+/// confirm what's already spendable. Immature coinbase rewards are instead
+/// found via `listtransactions`' `immature` category, which Core assigns
+/// specifically to coinbase outputs that haven't cleared 100 confirmations
+/// (an ordinary low-confirmation payment is never categorized that way).
+pub fn summarize_spendable<C: RpcApi>(
+    rpc: &C,
+    address: &Address,
+    network: Network,
+) -> Result<UtxoSummary, Error> {
+    let unspent = rpc.list_unspent(Some(0), None, Some(&[address]), None, None)?;
+    let spendable: Amount = unspent.iter().map(|utxo| utxo.amount).sum();
+
+    const PAGE_SIZE: usize = 100;
+    let mut immature = Amount::ZERO;
+    let mut immature_utxo_count = 0usize;
+    let mut skip = 0usize;
+    loop {
+        let page = rpc.list_transactions(None, Some(PAGE_SIZE), Some(skip), Some(true))?;
+        if page.is_empty() {
+            break;
+        }
+        for entry in &page {
+            if entry.detail.category != GetTransactionResultDetailCategory::Immature {
+                continue;
+            }
+            let entry_address = entry
+                .detail
+                .address
+                .clone()
+                .and_then(|a| a.require_network(network).ok());
+            if entry_address.as_ref() != Some(address) {
+                continue;
+            }
+            immature += entry.detail.amount.unsigned_abs();
+            immature_utxo_count += 1;
+        }
+        skip += page.len();
+    }
+
+    Ok(UtxoSummary {
+        address: address.to_string(),
+        spendable_btc: spendable.to_btc(),
+        immature_btc: immature.to_btc(),
+        immature_utxo_count,
+    })
+}
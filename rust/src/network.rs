@@ -0,0 +1,88 @@
+use bitcoincore_rpc::bitcoin::Network;
+use bitcoincore_rpc::{Error, RpcApi};
+use serde_json::Value;
+
+/// Ask the node which chain it is actually running on and map the answer to
+/// a `bitcoin::Network`.
+///
+/// The whole point of this helper is to stop us from trusting a hardcoded
+/// `--rpc-url`/`Network::Regtest` default: if someone points the CLI at a
+/// mainnet node, we want to find out from the node itself, not assume
+/// regtest and mine/spend real coins.
+pub fn detect_network<C: RpcApi>(rpc: &C) -> Result<Network, Error> {
+    let chain_info = rpc.call::<Value>("getblockchaininfo", &[])?;
+    let chain = chain_info
+        .get("chain")
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            Error::ReturnedError("getblockchaininfo response missing 'chain' field".to_string())
+        })?;
+    chain_to_network(chain)
+}
+
+/// Map `getblockchaininfo`'s `chain` string to a `bitcoin::Network`, split
+/// out of `detect_network` so the mapping can be unit tested without a node.
+fn chain_to_network(chain: &str) -> Result<Network, Error> {
+    match chain {
+        "main" => Ok(Network::Bitcoin),
+        "test" => Ok(Network::Testnet),
+        "signet" => Ok(Network::Signet),
+        "regtest" => Ok(Network::Regtest),
+        other => Err(Error::ReturnedError(format!(
+            "unrecognized chain '{}' reported by getblockchaininfo",
+            other
+        ))),
+    }
+}
+
+/// Fail hard on mainnet, and warn that mining/confirmations will be slow
+/// everywhere except regtest, where we can mine blocks on demand.
+pub fn guard_network(network: Network) -> Result<(), Box<dyn std::error::Error>> {
+    match network {
+        Network::Bitcoin => Err("refusing to run: connected node is on mainnet".into()),
+        Network::Testnet | Network::Signet => {
+            println!(
+                "Warning: connected node is on {:?}, not regtest. Mining and confirmations \
+                 will take real time, so this will be much slower than the regtest demo.",
+                network
+            );
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_to_network_maps_known_chains() {
+        assert_eq!(chain_to_network("main").unwrap(), Network::Bitcoin);
+        assert_eq!(chain_to_network("test").unwrap(), Network::Testnet);
+        assert_eq!(chain_to_network("signet").unwrap(), Network::Signet);
+        assert_eq!(chain_to_network("regtest").unwrap(), Network::Regtest);
+    }
+
+    #[test]
+    fn chain_to_network_rejects_unrecognized_chain() {
+        let err = chain_to_network("nonsense").unwrap_err();
+        assert!(matches!(err, Error::ReturnedError(_)));
+    }
+
+    #[test]
+    fn guard_network_rejects_mainnet() {
+        assert!(guard_network(Network::Bitcoin).is_err());
+    }
+
+    #[test]
+    fn guard_network_allows_testnet_and_signet_with_a_warning() {
+        assert!(guard_network(Network::Testnet).is_ok());
+        assert!(guard_network(Network::Signet).is_ok());
+    }
+
+    #[test]
+    fn guard_network_allows_regtest() {
+        assert!(guard_network(Network::Regtest).is_ok());
+    }
+}
@@ -0,0 +1,86 @@
+use bitcoincore_rpc::bitcoin::{Amount, SignedAmount};
+use serde::{Serialize, Serializer};
+use std::io::{self, Write};
+
+/// Format an `Amount` as BTC with full 8-decimal precision, so the json/table
+/// formats render amounts identically to each other.
+pub fn format_btc(amount: Amount) -> String {
+    format!("{:.8}", amount.to_btc())
+}
+
+/// Same as `format_btc`, for a `SignedAmount`.
+pub fn format_signed_btc(amount: SignedAmount) -> String {
+    format!("{:.8}", amount.to_btc())
+}
+
+fn serialize_btc<S: Serializer>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_btc(*amount))
+}
+
+fn serialize_signed_btc<S: Serializer>(amount: &SignedAmount, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_signed_btc(*amount))
+}
+
+/// All the fields extracted from the demo transaction in `report`, ready to
+/// be rendered in any of the supported output formats.
+#[derive(Debug, Serialize)]
+pub struct TransactionReport {
+    pub txid: String,
+    pub miner_input_address: String,
+    #[serde(serialize_with = "serialize_btc")]
+    pub miner_input_amount: Amount,
+    pub trader_output_address: String,
+    #[serde(serialize_with = "serialize_btc")]
+    pub trader_output_amount: Amount,
+    pub miner_change_address: String,
+    #[serde(serialize_with = "serialize_btc")]
+    pub miner_change_amount: Amount,
+    #[serde(serialize_with = "serialize_signed_btc")]
+    pub fee: SignedAmount,
+    pub block_height: u32,
+    pub block_hash: String,
+}
+
+impl TransactionReport {
+    /// The original ten-line layout, preserved for backward compatibility
+    /// with whatever already consumes `../out.txt`: bare `{}` on `to_btc()`,
+    /// not the fixed 8-decimal rendering the json/table formats use.
+    pub fn write_txt<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "{}", self.txid)?;
+        writeln!(out, "{}", self.miner_input_address)?;
+        writeln!(out, "{}", self.miner_input_amount.to_btc())?;
+        writeln!(out, "{}", self.trader_output_address)?;
+        writeln!(out, "{}", self.trader_output_amount.to_btc())?;
+        writeln!(out, "{}", self.miner_change_address)?;
+        writeln!(out, "{}", self.miner_change_amount.to_btc())?;
+        writeln!(out, "{}", self.fee.to_btc())?;
+        writeln!(out, "{}", self.block_height)?;
+        writeln!(out, "{}", self.block_hash)?;
+        Ok(())
+    }
+
+    pub fn write_json<W: Write>(&self, mut out: W) -> Result<(), Box<dyn std::error::Error>> {
+        serde_json::to_writer_pretty(&mut out, self)?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    pub fn write_table<W: Write>(&self, mut out: W) -> Result<(), Box<dyn std::error::Error>> {
+        use prettytable::{row, Table};
+
+        let mut table = Table::new();
+        table.add_row(row!["Field", "Value"]);
+        table.add_row(row!["Transaction ID", self.txid]);
+        table.add_row(row!["Miner input address", self.miner_input_address]);
+        table.add_row(row!["Miner input amount (BTC)", format_btc(self.miner_input_amount)]);
+        table.add_row(row!["Trader output address", self.trader_output_address]);
+        table.add_row(row!["Trader output amount (BTC)", format_btc(self.trader_output_amount)]);
+        table.add_row(row!["Miner change address", self.miner_change_address]);
+        table.add_row(row!["Miner change amount (BTC)", format_btc(self.miner_change_amount)]);
+        table.add_row(row!["Fee (BTC)", format_signed_btc(self.fee)]);
+        table.add_row(row!["Block height", self.block_height]);
+        table.add_row(row!["Block hash", self.block_hash]);
+        table.print(&mut out)?;
+        Ok(())
+    }
+}